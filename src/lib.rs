@@ -2,30 +2,54 @@
 extern crate test;
 
 // use std::collections::HashMap;
-use std::hash::{DefaultHasher, Hash, Hasher};
-
-#[derive(Default)]
-enum Bucket<K, V> {
-    #[default]
-    Unoccupied,
-    Deleted,
-    Occupied(Entry<K, V>),
-}
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+mod set;
+pub use set::HashSet;
 
-struct Entry<K, V> {
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+struct Slot<K, V> {
     key: K,
     value: V,
 }
 
-pub struct HashMap<K, V>
+/// Number of control bytes probed together before advancing to the next group.
+const GROUP_SIZE: usize = 16;
+/// Control byte for a slot that has never held an entry.
+const EMPTY: u8 = 0xFF;
+/// Control byte for a slot whose entry was removed (a tombstone).
+const DELETED: u8 = 0x80;
+/// Mask selecting H2, the low 7 bits of a hash stored directly in a full control byte.
+const H2_MASK: u64 = 0x7F;
+/// A table rehashes once `len + tombstones` crosses this percentage of capacity.
+const LOAD_FACTOR_MAX: u64 = 65;
+
+/// Where [`HashMap::find_slot`] landed: an existing entry, or the first
+/// empty-or-deleted slot found along the probe sequence.
+enum Probe {
+    Occupied(usize),
+    Vacant(usize),
+}
+
+pub struct HashMap<K, V, S = RandomState>
 where
     K: Hash + PartialEq + Eq,
 {
-    buckets: Vec<Bucket<K, V>>,
+    /// Parallel to `slots`: `EMPTY`, `DELETED`, or `H2` of the stored hash.
+    ctrl: Vec<u8>,
+    slots: Vec<Option<Slot<K, V>>>,
     len: usize,
+    /// Number of `DELETED` control bytes, tracked so a table saturated with
+    /// tombstones rehashes to reclaim them instead of only growing on `len`.
+    tombstones: usize,
+    hash_builder: S,
 }
 
-impl<K, V> HashMap<K, V>
+impl<K, V> HashMap<K, V, RandomState>
 where
     K: Hash + Eq + PartialEq,
 {
@@ -33,118 +57,227 @@ where
         Self::default()
     }
 
-    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        self.ensure_capacity();
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::default())
+    }
+}
 
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        let hash = hasher.finish();
-        let mut pos = hash as usize % self.buckets.len();
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Eq + PartialEq,
+    S: BuildHasher,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        const DEFAULT_CAPACITY: usize = 1024;
+        Self {
+            ctrl: vec![EMPTY; DEFAULT_CAPACITY],
+            slots: (0..DEFAULT_CAPACITY).map(|_| None).collect(),
+            len: 0,
+            tombstones: 0,
+            hash_builder,
+        }
+    }
 
-        // quadratic probing for unoccupied bucket
-        let mut probe_count = 0usize;
-        while let Bucket::Occupied(p) = &self.buckets[pos]
-            && p.key != key
-        {
-            probe_count += 1;
-            pos = (pos + probe_count.pow(2)) % self.buckets.len();
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let buckets = Self::buckets_for(capacity);
+        Self {
+            ctrl: vec![EMPTY; buckets],
+            slots: (0..buckets).map(|_| None).collect(),
+            len: 0,
+            tombstones: 0,
+            hash_builder,
         }
+    }
 
-        self.len += 1;
-        let existing = std::mem::replace(
-            &mut self.buckets[pos],
-            Bucket::Occupied(Entry { key, value }),
-        );
+    /// Smallest power-of-two bucket count that holds `len` elements without
+    /// crossing `LOAD_FACTOR_MAX`. Must be a power of two: `find_slot`'s
+    /// triangular group-probe only enumerates every group when `num_groups`
+    /// is a power of two, so rounding to a mere multiple of `GROUP_SIZE`
+    /// (e.g. 97 groups) leaves about half of the groups unreachable.
+    fn buckets_for(len: usize) -> usize {
+        let needed = (len as u64 * 100).div_ceil(LOAD_FACTOR_MAX).max(GROUP_SIZE as u64) as usize;
+        needed.next_power_of_two()
+    }
 
-        match existing {
-            Bucket::Occupied(p) => Some(p.value),
-            _ => None,
-        }
+    fn hash_of<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        self.hash_builder.hash_one(key)
+    }
+
+    /// Splits a hash into H1 (the starting group) and H2 (the byte stored
+    /// in `ctrl` for a full slot), SwissTable-style.
+    fn h1(hash: u64) -> usize {
+        (hash >> 7) as usize
     }
 
-    pub fn get(&self, key: &K) -> Option<&V> {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        let hash = hasher.finish();
-        let expected_pos = hash as usize % self.buckets.len();
+    fn h2(hash: u64) -> u8 {
+        (hash & H2_MASK) as u8
+    }
 
-        // quadratic probing for bucket
-        let mut pos = expected_pos;
+    /// Probes groups of `GROUP_SIZE` control bytes starting at H1's group,
+    /// advancing by a triangular offset between groups. An `EMPTY` byte
+    /// inside a group is a definitive stop: the key cannot be further along
+    /// the sequence since insertion always fills the first empty-or-deleted
+    /// slot along it.
+    fn find_slot<Q>(&self, key: &Q, hash: u64) -> Probe
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let num_groups = self.ctrl.len() / GROUP_SIZE;
+        let h2 = Self::h2(hash);
+        let expected_group = Self::h1(hash) % num_groups;
+        let mut group = expected_group;
+        let mut tombstone = None;
         let mut probe_count = 0usize;
+
         loop {
-            probe_count += 1;
-            match &self.buckets[pos] {
-                Bucket::Unoccupied => return None,
-                Bucket::Deleted => pos = (pos + probe_count.pow(2)) % self.buckets.len(),
-                Bucket::Occupied(p) => {
-                    if key == &p.key {
-                        return Some(&p.value);
+            let start = group * GROUP_SIZE;
+            for idx in start..start + GROUP_SIZE {
+                match self.ctrl[idx] {
+                    EMPTY => return Probe::Vacant(tombstone.unwrap_or(idx)),
+                    DELETED => tombstone = tombstone.or(Some(idx)),
+                    byte if byte == h2 => {
+                        if let Some(slot) = &self.slots[idx]
+                            && key == slot.key.borrow()
+                        {
+                            return Probe::Occupied(idx);
+                        }
                     }
-                    pos = (pos + probe_count.pow(2)) % self.buckets.len()
+                    _ => {}
                 }
             }
 
-            // we went through the full map
-            if pos == expected_pos {
+            probe_count += 1;
+            group = (group + probe_count) % num_groups;
+
+            // we went through every group without hitting an empty slot
+            if group == expected_group {
                 break;
             }
         }
-        None
+
+        Probe::Vacant(tombstone.expect("a full table without tombstones should have resized"))
     }
 
-    pub fn remove(&mut self, key: &K) -> Option<V> {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        let hash = hasher.finish();
-        let expected_pos = hash as usize % self.buckets.len();
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.ensure_capacity();
 
-        // quadratic probing for bucket
-        let mut pos = expected_pos;
-        let mut probe_count = 0usize;
-        loop {
-            probe_count += 1;
-            match &self.buckets[pos] {
-                Bucket::Unoccupied => return None,
-                Bucket::Deleted => pos = (pos + probe_count.pow(2)) % self.buckets.len(),
-                Bucket::Occupied(p) => {
-                    if key == &p.key {
-                        let existing = std::mem::replace(&mut self.buckets[pos], Bucket::Deleted);
-                        if let Bucket::Occupied(p) = existing {
-                            return Some(p.value);
-                        }
-                    }
-                    pos = (pos + probe_count.pow(2)) % self.buckets.len()
+        let hash = self.hash_of(&key);
+        match self.find_slot(&key, hash) {
+            Probe::Occupied(idx) => {
+                let slot = self.slots[idx]
+                    .as_mut()
+                    .expect("occupied probe result must point at a full slot");
+                Some(std::mem::replace(&mut slot.value, value))
+            }
+            Probe::Vacant(idx) => {
+                if self.ctrl[idx] == DELETED {
+                    self.tombstones -= 1;
                 }
+                self.ctrl[idx] = Self::h2(hash);
+                self.slots[idx] = Some(Slot { key, value });
+                self.len += 1;
+                None
             }
+        }
+    }
 
-            // we went through the full map
-            if pos == expected_pos {
-                break;
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hash_of(key);
+        match self.find_slot(key, hash) {
+            Probe::Occupied(idx) => self.slots[idx].as_ref().map(|slot| &slot.value),
+            Probe::Vacant(_) => None,
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hash_of(key);
+        match self.find_slot(key, hash) {
+            Probe::Occupied(idx) => {
+                self.ctrl[idx] = DELETED;
+                self.len -= 1;
+                self.tombstones += 1;
+                self.slots[idx].take().map(|slot| slot.value)
             }
+            Probe::Vacant(_) => None,
+        }
+    }
+
+    /// Rebuilds the table at `new_capacity` buckets, purging tombstones.
+    fn rehash_into(&mut self, new_capacity: usize) {
+        let old_slots =
+            std::mem::replace(&mut self.slots, (0..new_capacity).map(|_| None).collect());
+        self.ctrl = vec![EMPTY; new_capacity];
+        self.len = 0;
+        self.tombstones = 0;
+
+        for slot in old_slots.into_iter().flatten() {
+            self.insert(slot.key, slot.value);
         }
-        None
     }
 
     pub fn ensure_capacity(&mut self) {
-        const LOAD_FACTOR_MAX: u64 = 65;
-        let load_factor = self.len * 100 / self.buckets.len();
+        let used = self.len + self.tombstones;
+        let load_factor = used * 100 / self.ctrl.len();
         if load_factor as u64 >= LOAD_FACTOR_MAX {
-            let mut new_buckets = Vec::with_capacity(self.buckets.len() * 2);
-            new_buckets.resize_with(self.buckets.len() * 2, || Bucket::Unoccupied);
-            let old_buckets = std::mem::replace(&mut self.buckets, new_buckets);
-
-            // insert the old elements
-            self.len = 0;
-            for b in old_buckets {
-                if let Bucket::Occupied(p) = b {
-                    self.insert(p.key, p.value);
-                }
-            }
+            // a table saturated with tombstones doesn't need to grow, only
+            // a same-size rehash to reclaim them
+            let live_load_factor = self.len * 100 / self.ctrl.len();
+            let new_capacity = if (live_load_factor as u64) < LOAD_FACTOR_MAX {
+                self.ctrl.len()
+            } else {
+                self.ctrl.len() * 2
+            };
+            self.rehash_into(new_capacity);
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        let buckets = Self::buckets_for(self.len + additional);
+        if buckets > self.ctrl.len() {
+            self.rehash_into(buckets);
+        }
+    }
+
+    /// Shrinks the table to the smallest capacity that fits the current
+    /// elements, purging any accumulated tombstones in the process.
+    pub fn shrink_to_fit(&mut self) {
+        let buckets = Self::buckets_for(self.len);
+        if buckets < self.ctrl.len() {
+            self.rehash_into(buckets);
         }
     }
 
+    /// Removes all elements, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.ctrl.fill(EMPTY);
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+        self.tombstones = 0;
+    }
+
     pub fn capacity(&self) -> usize {
-        self.buckets.len()
+        self.ctrl.len()
     }
 
     pub fn len(&self) -> usize {
@@ -158,62 +291,73 @@ where
     pub fn iter<'a>(&'a self) -> Iter<'a, K, V> {
         self.into_iter()
     }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        self.ensure_capacity();
+
+        let hash = self.hash_of(&key);
+        match self.find_slot(&key, hash) {
+            Probe::Occupied(pos) => Entry::Occupied(OccupiedEntry { map: self, pos }),
+            Probe::Vacant(pos) => Entry::Vacant(VacantEntry {
+                map: self,
+                pos,
+                key,
+                hash,
+            }),
+        }
+    }
 }
 
-impl<K: Hash + Eq, V> Default for HashMap<K, V> {
+impl<K: Hash + Eq, V, S: BuildHasher + Default> Default for HashMap<K, V, S> {
     fn default() -> Self {
-        let mut buckets = Vec::with_capacity(1024);
-        buckets.resize_with(1024, || Bucket::Unoccupied);
-        Self { buckets, len: 0 }
+        Self::with_hasher(S::default())
     }
 }
 
-impl<'a, K: Hash + PartialEq + Eq, V> IntoIterator for &'a HashMap<K, V> {
+impl<'a, K: Hash + PartialEq + Eq, V, S> IntoIterator for &'a HashMap<K, V, S> {
     type Item = Pair<&'a K, &'a V>;
 
     type IntoIter = Iter<'a, K, V>;
 
     fn into_iter(self) -> Self::IntoIter {
         Iter {
-            buckets: self.buckets.iter(),
+            slots: self.slots.iter(),
         }
     }
 }
 
 pub struct Iter<'a, K, V> {
-    buckets: std::slice::Iter<'a, Bucket<K, V>>,
+    slots: std::slice::Iter<'a, Option<Slot<K, V>>>,
 }
 
 impl<'a, K, V> Iterator for Iter<'a, K, V> {
     type Item = Pair<&'a K, &'a V>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(bucket) = self.buckets.next() {
-            if let Bucket::Occupied(item) = bucket {
-                return Some(Pair {
-                    key: &item.key,
-                    value: &item.value,
-                });
-            }
+        if let Some(item) = self.slots.by_ref().flatten().next() {
+            return Some(Pair {
+                key: &item.key,
+                value: &item.value,
+            });
         }
         None
     }
 }
 
-impl<K: Hash + PartialEq + Eq, V> IntoIterator for HashMap<K, V> {
+impl<K: Hash + PartialEq + Eq, V, S> IntoIterator for HashMap<K, V, S> {
     type Item = Pair<K, V>;
 
     type IntoIter = IntoIter<K, V>;
 
     fn into_iter(self) -> Self::IntoIter {
         IntoIter {
-            buckets: self.buckets.into_iter(),
+            slots: self.slots.into_iter(),
         }
     }
 }
 
 pub struct IntoIter<K, V> {
-    buckets: std::vec::IntoIter<Bucket<K, V>>,
+    slots: std::vec::IntoIter<Option<Slot<K, V>>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -226,21 +370,151 @@ impl<K, V> Iterator for IntoIter<K, V> {
     type Item = Pair<K, V>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(bucket) = self.buckets.next() {
-            if let Bucket::Occupied(item) = bucket {
-                return Some(Pair {
-                    key: item.key,
-                    value: item.value,
-                });
-            }
+        if let Some(item) = self.slots.by_ref().flatten().next() {
+            return Some(Pair {
+                key: item.key,
+                value: item.value,
+            });
         }
         None
     }
 }
 
+/// A view into a single entry of a [`HashMap`], obtained via [`HashMap::entry`].
+///
+/// The entry remembers the bucket resolved during the initial probe, so
+/// inserting into a vacant entry or updating an occupied one doesn't re-hash
+/// or re-probe the key.
+pub enum Entry<'a, K: Hash + Eq, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Hash + Eq + PartialEq,
+    S: BuildHasher,
+{
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Hash + Eq + PartialEq,
+    V: Default,
+    S: BuildHasher,
+{
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// An occupied entry, pointing at the bucket discovered by [`HashMap::entry`].
+pub struct OccupiedEntry<'a, K: Hash + Eq, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    pos: usize,
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S>
+where
+    K: Hash + Eq + PartialEq,
+{
+    pub fn get(&self) -> &V {
+        self.map.slots[self.pos]
+            .as_ref()
+            .map(|slot| &slot.value)
+            .expect("occupied entry must point at a full slot")
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.slots[self.pos]
+            .as_mut()
+            .map(|slot| &mut slot.value)
+            .expect("occupied entry must point at a full slot")
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.slots[self.pos]
+            .as_mut()
+            .map(|slot| &mut slot.value)
+            .expect("occupied entry must point at a full slot")
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        let slot = self.map.slots[self.pos]
+            .as_mut()
+            .expect("occupied entry must point at a full slot");
+        std::mem::replace(&mut slot.value, value)
+    }
+
+    pub fn remove(self) -> V {
+        self.map.ctrl[self.pos] = DELETED;
+        self.map.len -= 1;
+        self.map.tombstones += 1;
+        self.map.slots[self.pos]
+            .take()
+            .map(|slot| slot.value)
+            .expect("occupied entry must point at a full slot")
+    }
+}
+
+/// A vacant entry, pointing at the empty-or-deleted bucket discovered by
+/// [`HashMap::entry`].
+pub struct VacantEntry<'a, K: Hash + Eq, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    pos: usize,
+    key: K,
+    hash: u64,
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+    K: Hash + Eq + PartialEq,
+{
+    pub fn insert(self, value: V) -> &'a mut V {
+        if self.map.ctrl[self.pos] == DELETED {
+            self.map.tombstones -= 1;
+        }
+        self.map.ctrl[self.pos] = (self.hash & H2_MASK) as u8;
+        self.map.slots[self.pos] = Some(Slot {
+            key: self.key,
+            value,
+        });
+        self.map.len += 1;
+
+        self.map.slots[self.pos]
+            .as_mut()
+            .map(|slot| &mut slot.value)
+            .expect("just inserted")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::hash::Hasher;
     use test::Bencher;
 
     #[test]
@@ -369,6 +643,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_borrow_str_lookup() {
+        // given
+        let mut map: HashMap<String, i32> = HashMap::new();
+        map.insert("one".to_string(), 1);
+
+        // when/then
+        assert_eq!(
+            Some(&1),
+            map.get("one"),
+            "looks up a String key via &str without allocating"
+        );
+        assert!(map.contains_key("one"), "contains_key accepts &str too");
+        assert!(!map.contains_key("two"));
+        assert_eq!(
+            Some(1),
+            map.remove("one"),
+            "removes a String key via &str without allocating"
+        );
+        assert_eq!(None, map.get("one"));
+    }
+
     #[test]
     fn test_into_iter() {
         // given
@@ -393,6 +689,193 @@ mod tests {
         assert_eq!(want_pairs, got_pairs);
     }
 
+    #[test]
+    fn test_entry() {
+        // given
+        let mut map = HashMap::new();
+
+        // when/then
+        *map.entry("a".to_string()).or_insert(0) += 1;
+        *map.entry("a".to_string()).or_insert(0) += 1;
+        assert_eq!(Some(&2), map.get("a"), "or_insert updates an existing entry");
+
+        map.entry("b".to_string()).or_insert_with(|| 5);
+        assert_eq!(
+            Some(&5),
+            map.get("b"),
+            "or_insert_with populates a vacant entry"
+        );
+
+        map.entry("a".to_string()).and_modify(|v| *v *= 10);
+        assert_eq!(
+            Some(&20),
+            map.get("a"),
+            "and_modify only runs for an occupied entry"
+        );
+        map.entry("c".to_string()).and_modify(|v| *v *= 10);
+        assert_eq!(
+            None,
+            map.get("c"),
+            "and_modify does not insert a vacant entry"
+        );
+
+        *map.entry("c".to_string()).or_default() += 1;
+        assert_eq!(Some(&1), map.get("c"), "or_default inserts V::default()");
+    }
+
+    #[test]
+    fn test_entry_does_not_duplicate_through_tombstone() {
+        #[derive(Debug, Eq)]
+        struct KeyWithFixedHash {
+            hash: [u8; 4],
+            key: i32,
+        }
+
+        impl Hash for KeyWithFixedHash {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                state.write(&self.hash)
+            }
+        }
+
+        impl PartialEq for KeyWithFixedHash {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key
+            }
+        }
+
+        // given: two colliding keys, with the first removed so a tombstone
+        // precedes the second in its probe chain
+        let mut map = HashMap::new();
+        map.insert(
+            KeyWithFixedHash {
+                hash: [1, 2, 3, 4],
+                key: 1,
+            },
+            100,
+        );
+        map.insert(
+            KeyWithFixedHash {
+                hash: [1, 2, 3, 4],
+                key: 2,
+            },
+            200,
+        );
+        map.remove(&KeyWithFixedHash {
+            hash: [1, 2, 3, 4],
+            key: 1,
+        });
+
+        // when
+        *map.entry(KeyWithFixedHash {
+            hash: [1, 2, 3, 4],
+            key: 2,
+        })
+        .or_insert(0) += 1;
+
+        // then
+        assert_eq!(
+            1,
+            map.len(),
+            "entry must not create a duplicate past a tombstone"
+        );
+        assert_eq!(
+            Some(&201),
+            map.get(&KeyWithFixedHash {
+                hash: [1, 2, 3, 4],
+                key: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_capacity_management() {
+        // given
+        let mut map = HashMap::with_capacity(10);
+        let initial_capacity = map.capacity();
+
+        // when/then
+        map.reserve(1000);
+        assert!(
+            map.capacity() > initial_capacity,
+            "reserve grows capacity ahead of inserts"
+        );
+
+        for i in 0..10 {
+            map.insert(i, i);
+        }
+        map.clear();
+        assert_eq!(0, map.len(), "clear empties the map");
+        assert_eq!(None, map.get(&0), "clear removes all elements");
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        // given
+        let mut map = HashMap::with_capacity(1000);
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        for i in 0..1000 {
+            map.remove(&i);
+        }
+
+        // when
+        map.shrink_to_fit();
+
+        // then
+        assert!(
+            map.capacity() < 100,
+            "shrink_to_fit reclaims capacity after removing everything"
+        );
+    }
+
+    #[test]
+    fn test_with_capacity_handles_collisions_past_load_factor() {
+        #[derive(Debug, Eq)]
+        struct KeyWithFixedHash {
+            hash: [u8; 4],
+            key: i32,
+        }
+
+        impl Hash for KeyWithFixedHash {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                state.write(&self.hash)
+            }
+        }
+
+        impl PartialEq for KeyWithFixedHash {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key
+            }
+        }
+
+        // given
+        let mut map = HashMap::with_capacity(1000);
+
+        // when
+        for i in 0..900 {
+            map.insert(
+                KeyWithFixedHash {
+                    hash: [1, 2, 3, 4],
+                    key: i,
+                },
+                i,
+            );
+        }
+
+        // then
+        for i in 0..900 {
+            assert_eq!(
+                Some(&i),
+                map.get(&KeyWithFixedHash {
+                    hash: [1, 2, 3, 4],
+                    key: i,
+                }),
+                "every colliding key stays reachable past the load factor threshold"
+            );
+        }
+    }
+
     #[test]
     fn test_iter() {
         // given
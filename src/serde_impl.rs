@@ -0,0 +1,104 @@
+//! `serde` support, enabled by the `serde` feature. Mirrors hashbrown's
+//! `external_trait_impls::serde` module: serialize as a map, deserialize by
+//! repeatedly calling `insert` while draining a `MapAccess`.
+
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::HashMap;
+
+impl<K, V, S> Serialize for HashMap<K, V, S>
+where
+    K: Serialize + Hash + Eq,
+    V: Serialize,
+    S: BuildHasher,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for pair in self.iter() {
+            map.serialize_entry(pair.key, pair.value)?;
+        }
+        map.end()
+    }
+}
+
+struct HashMapVisitor<K: Hash + Eq, V, S> {
+    marker: PhantomData<HashMap<K, V, S>>,
+}
+
+impl<'de, K, V, S> Visitor<'de> for HashMapVisitor<K, V, S>
+where
+    K: Deserialize<'de> + Hash + Eq,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = HashMap<K, V, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map_access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map =
+            HashMap::with_capacity_and_hasher(map_access.size_hint().unwrap_or(0), S::default());
+        while let Some((key, value)) = map_access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+impl<'de, K, V, S> Deserialize<'de> for HashMap<K, V, S>
+where
+    K: Deserialize<'de> + Hash + Eq,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(HashMapVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        // given
+        let mut map = HashMap::new();
+        for i in 0..10 {
+            map.insert(i.to_string(), i);
+        }
+
+        // when
+        let json = serde_json::to_string(&map).expect("map serializes");
+        let round_tripped: HashMap<String, i32> =
+            serde_json::from_str(&json).expect("map deserializes");
+
+        // then
+        assert_eq!(map.len(), round_tripped.len());
+        for i in 0..10 {
+            assert_eq!(
+                map.get(&i.to_string()),
+                round_tripped.get(&i.to_string()),
+                "round-tripped map agrees with the original"
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use crate::HashMap;
+
+/// A hash set implemented as a thin wrapper over [`HashMap<T, ()>`](crate::HashMap),
+/// mirroring how the standard library splits its set out over its map.
+pub struct HashSet<T, S = RandomState>
+where
+    T: Hash + Eq,
+{
+    map: HashMap<T, (), S>,
+}
+
+impl<T> HashSet<T, RandomState>
+where
+    T: Hash + Eq,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, S> HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    /// Inserts `value`, returning `true` if it wasn't already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Removes `value`, returning `true` if it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+
+    pub fn union<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().chain(other.difference(self))
+    }
+
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().filter(move |t| other.contains(*t))
+    }
+
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().filter(move |t| !other.contains(*t))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|t| other.contains(t))
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher + Default> Default for HashSet<T, S> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter {
+            inner: self.map.iter(),
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: crate::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| pair.key)
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> IntoIterator for HashSet<T, S> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+pub struct IntoIter<T> {
+    inner: crate::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| pair.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integration() {
+        // given
+        let mut set = HashSet::new();
+
+        // when/then
+        assert!(set.insert(1), "inserts a new element");
+        assert!(!set.insert(1), "reports duplicates");
+        assert!(set.contains(&1), "finds an inserted element");
+        assert!(set.remove(&1), "removes an existing element");
+        assert!(!set.contains(&1), "does not find a removed element");
+        assert_eq!(0, set.len());
+    }
+
+    fn set_of(values: &[i32]) -> HashSet<i32> {
+        let mut set = HashSet::new();
+        for &v in values {
+            set.insert(v);
+        }
+        set
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        // given
+        let a = set_of(&[1, 2, 3]);
+        let b = set_of(&[2, 3, 4]);
+
+        // when/then
+        let mut union: Vec<_> = a.union(&b).copied().collect();
+        union.sort();
+        assert_eq!(vec![1, 2, 3, 4], union);
+
+        let mut intersection: Vec<_> = a.intersection(&b).copied().collect();
+        intersection.sort();
+        assert_eq!(vec![2, 3], intersection);
+
+        let mut difference: Vec<_> = a.difference(&b).copied().collect();
+        difference.sort();
+        assert_eq!(vec![1], difference);
+
+        assert!(!a.is_subset(&b), "a has an element not in b");
+        let c = set_of(&[2, 3]);
+        assert!(c.is_subset(&a), "every element of c is in a");
+    }
+}